@@ -0,0 +1,87 @@
+use crate::utils::{CommandOutput, exec_timeout};
+use std::fmt::Debug;
+use std::process::Command;
+use std::time::Duration;
+
+/// Runs the external commands built by [`crate::context::git::Repo::exec_git`]
+/// and [`crate::context::jj::Repo::exec_jj`].
+///
+/// The real implementation just shells out via `exec_timeout`. Tests swap in a
+/// [`MockCommandRunner`] that replays a recorded `args -> CommandOutput` map
+/// instead, so the jj two-pass templating (the `\x1f`/`\x1e`/`\n` split logic
+/// and ahead/behind merging) and the git ahead/behind and status paths can be
+/// unit-tested without a `git`/`jj` binary installed, mirroring how
+/// `Context::exec_cmd` is already mockable.
+pub trait CommandRunner: Debug {
+    fn run(&self, command: Command, timeout: Duration) -> Option<CommandOutput>;
+}
+
+/// Default [`CommandRunner`] that actually spawns the process.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemCommandRunner;
+
+impl CommandRunner for SystemCommandRunner {
+    fn run(&self, mut command: Command, timeout: Duration) -> Option<CommandOutput> {
+        exec_timeout(&mut command, timeout)
+    }
+}
+
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub struct MockCommandRunner {
+    pub responses: std::collections::HashMap<Vec<String>, CommandOutput>,
+}
+
+#[cfg(test)]
+impl MockCommandRunner {
+    pub fn with(self, args: &[&str], output: CommandOutput) -> Self {
+        self.with_vec(args.iter().map(|a| (*a).to_string()).collect(), output)
+    }
+
+    pub fn with_vec(mut self, args: Vec<String>, output: CommandOutput) -> Self {
+        self.responses.insert(args, output);
+        self
+    }
+}
+
+#[cfg(test)]
+impl CommandRunner for MockCommandRunner {
+    fn run(&self, command: Command, _timeout: Duration) -> Option<CommandOutput> {
+        let args: Vec<String> = std::iter::once(command.get_program())
+            .chain(command.get_args())
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect();
+        self.responses.get(&args).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replays_recorded_output_for_matching_args() {
+        let runner = MockCommandRunner::default().with(
+            &["jj", "log", "-r", "@"],
+            CommandOutput {
+                stdout: "ok".into(),
+                stderr: String::new(),
+            },
+        );
+
+        let mut command = Command::new("jj");
+        command.args(["log", "-r", "@"]);
+
+        let output = runner.run(command, Duration::from_millis(500));
+
+        assert_eq!(output.map(|o| o.stdout), Some("ok".to_string()));
+    }
+
+    #[test]
+    fn returns_none_for_unrecorded_args() {
+        let runner = MockCommandRunner::default();
+        let command = Command::new("jj");
+
+        assert!(runner.run(command, Duration::from_millis(500)).is_none());
+    }
+}
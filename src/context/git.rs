@@ -1,5 +1,5 @@
 use crate::context::Context;
-use crate::utils::{CommandOutput, create_command, exec_timeout};
+use crate::utils::{CommandOutput, create_command};
 use gix::{
     Repository, ThreadSafeRepository,
     repository::Kind,
@@ -44,6 +44,63 @@ pub struct Repo {
 pub struct Remote {
     pub branch: Option<String>,
     pub name: Option<String>,
+
+    /// The forge hosting this remote and the `owner`/`repo` parsed from its URL,
+    /// if the remote's fetch URL could be resolved and parsed.
+    pub forge: Option<ForgeInfo>,
+}
+
+/// A code-hosting forge inferred from a remote URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Forge {
+    GitHub,
+    GitLab,
+    Bitbucket,
+    SourceHut,
+    /// Gitea or Forgejo — the two are indistinguishable from the URL alone.
+    GiteaOrForgejo,
+    /// Any other (typically self-hosted) forge, identified by its bare hostname.
+    Other(String),
+}
+
+/// The forge a remote points at, plus the `owner`/`repo` parsed out of its URL path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForgeInfo {
+    pub forge: Forge,
+    pub owner: String,
+    pub repo: String,
+}
+
+fn classify_forge(host: &str) -> Forge {
+    match host {
+        "github.com" => Forge::GitHub,
+        "gitlab.com" => Forge::GitLab,
+        "bitbucket.org" => Forge::Bitbucket,
+        "sr.ht" | "git.sr.ht" => Forge::SourceHut,
+        host if host.contains("gitea") || host.contains("forgejo") => Forge::GiteaOrForgejo,
+        host => Forge::Other(host.to_string()),
+    }
+}
+
+/// Parses a remote's fetch URL into its hosting [`Forge`] plus `owner`/`repo`.
+///
+/// Handles SSH shorthand (`git@host:owner/repo.git`), full `ssh://`/`https://`
+/// URLs, and strips the trailing `.git` suffix.
+fn parse_forge_url(url: &gix::Url) -> Option<ForgeInfo> {
+    let host = url.host()?.to_string();
+    let path = url.path.to_string();
+    let path = path.trim_start_matches('/').trim_end_matches(".git");
+    let (owner, repo) = path.rsplit_once('/')?;
+
+    if owner.is_empty() || repo.is_empty() {
+        return None;
+    }
+
+    Some(ForgeInfo {
+        forge: classify_forge(&host),
+        owner: owner.to_string(),
+        repo: repo.to_string(),
+    })
 }
 
 pub fn init_repo(current_dir: &Path) -> Result<Repo, Box<gix::discover::Error>> {
@@ -126,7 +183,52 @@ fn get_remote_repository_info(
         .branch_remote_name(branch_name.shorten(), gix::remote::Direction::Fetch)
         .map(|n| n.as_bstr().to_string());
 
-    Some(Remote { branch, name })
+    let forge = name
+        .as_deref()
+        .and_then(|name| repository.find_remote(name).ok())
+        .and_then(|remote| remote.url(gix::remote::Direction::Fetch).cloned())
+        .and_then(|url| parse_forge_url(&url));
+
+    Some(Remote {
+        branch,
+        name,
+        forge,
+    })
+}
+
+/// Counts commits reachable from `tip` but not from `hidden` (and its ancestors),
+/// bailing out with `None` once `deadline` passes rather than walking forever.
+fn count_unique_commits(
+    repository: &Repository,
+    tip: gix::ObjectId,
+    hidden: gix::ObjectId,
+    deadline: std::time::Instant,
+) -> Option<usize> {
+    let walk = repository
+        .rev_walk([tip])
+        .with_hidden([hidden])
+        .all()
+        .ok()?;
+
+    let mut count = 0;
+    for info in walk {
+        if std::time::Instant::now() > deadline {
+            return None;
+        }
+        info.ok()?;
+        count += 1;
+    }
+
+    Some(count)
+}
+
+/// Parses the `behind\tahead` counts out of
+/// `git rev-list --left-right --count @{u}...HEAD` output.
+fn parse_left_right_counts(stdout: &str) -> Option<(usize, usize)> {
+    let mut counts = stdout.split_whitespace();
+    let behind: usize = counts.next()?.parse().ok()?;
+    let ahead: usize = counts.next()?.parse().ok()?;
+    Some((ahead, behind))
 }
 
 impl Repo {
@@ -135,10 +237,68 @@ impl Repo {
         self.repo.to_thread_local()
     }
 
+    /// Computes how many commits the local branch is ahead/behind its upstream.
+    ///
+    /// Tries purely in-process via the already-open `ThreadSafeRepository` first,
+    /// so prompts on large repos don't pay a subprocess per render, and falls
+    /// back to the `git rev-list --count --left-right` subprocess path if there
+    /// is no branch, no upstream, a tip can't be resolved, or the walk is
+    /// aborted by `command_timeout` (so a pathological history can't hang the
+    /// prompt). Returns `None` if both paths fail.
+    pub fn divergence(&self, context: &Context) -> Option<(usize, usize)> {
+        self.divergence_in_process(context)
+            .or_else(|| self.divergence_subprocess(context))
+    }
+
+    fn divergence_in_process(&self, context: &Context) -> Option<(usize, usize)> {
+        let repository = self.open();
+        let head_name = get_current_branch(&repository)?;
+        let upstream_name = repository
+            .branch_remote_ref_name(head_name.as_ref(), gix::remote::Direction::Fetch)
+            .and_then(std::result::Result::ok)?;
+
+        let local = repository
+            .find_reference(head_name.as_ref())
+            .ok()?
+            .peel_to_id()
+            .ok()?
+            .detach();
+        let upstream = repository
+            .find_reference(upstream_name.as_ref())
+            .ok()?
+            .peel_to_id()
+            .ok()?
+            .detach();
+
+        if local == upstream {
+            return Some((0, 0));
+        }
+
+        let deadline = std::time::Instant::now()
+            + Duration::from_millis(context.root_config.command_timeout);
+
+        let ahead = count_unique_commits(&repository, local, upstream, deadline)?;
+        let behind = count_unique_commits(&repository, upstream, local, deadline)?;
+
+        Some((ahead, behind))
+    }
+
+    /// Subprocess fallback for [`Repo::divergence`], kept as a method (rather
+    /// than deleted) precisely so that any caller still gets a correct answer
+    /// on repository shapes the in-process walk above can't yet resolve.
+    fn divergence_subprocess(&self, context: &Context) -> Option<(usize, usize)> {
+        let output = self.exec_git(
+            context,
+            ["rev-list", "--left-right", "--count", "@{u}...HEAD"],
+        )?;
+        parse_left_right_counts(&output.stdout)
+    }
+
     /// Wrapper to execute external git commands.
     /// Handles adding the appropriate `--git-dir` and `--work-tree` flags to the command.
     /// Also handles additional features required for security, such as disabling `fsmonitor`.
-    /// At this time, mocking is not supported.
+    /// Runs the command through `context.command_runner`, so tests can swap in a
+    /// `MockCommandRunner` instead of spawning a real `git` process.
     pub fn exec_git<T: AsRef<OsStr> + Debug>(
         &self,
         context: &Context,
@@ -170,9 +330,72 @@ impl Repo {
         command.args(git_args);
         log::trace!("Executing git command: {command:?}");
 
-        exec_timeout(
-            &mut command,
-            Duration::from_millis(context.root_config.command_timeout),
-        )
+        context
+            .command_runner
+            .run(command, Duration::from_millis(context.root_config.command_timeout))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::command::MockCommandRunner;
+
+    #[test]
+    fn parses_left_right_counts() {
+        assert_eq!(parse_left_right_counts("3\t5\n"), Some((5, 3)));
+        assert_eq!(parse_left_right_counts("0\t0"), Some((0, 0)));
+        assert_eq!(parse_left_right_counts(""), None);
+        assert_eq!(parse_left_right_counts("not-a-number\t1"), None);
+    }
+
+    #[test]
+    fn exec_git_replays_mocked_output_for_a_discovered_repository() {
+        let tmp = std::env::temp_dir().join(format!(
+            "starship-exec-git-test-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&tmp).unwrap();
+        std::process::Command::new("git")
+            .args(["init", "--quiet"])
+            .current_dir(&tmp)
+            .status()
+            .unwrap();
+
+        let repo = init_repo(&tmp).expect("git repo should be discoverable");
+
+        let mut args = vec![
+            "git".to_string(),
+            "-C".to_string(),
+            tmp.to_string_lossy().into_owned(),
+            "--git-dir".to_string(),
+            repo.path.to_string_lossy().into_owned(),
+            "-c".to_string(),
+            "core.fsmonitor=".to_string(),
+        ];
+        if let Some(wt) = repo.workdir.as_ref() {
+            args.push("--work-tree".to_string());
+            args.push(wt.to_string_lossy().into_owned());
+        }
+        args.push("status".to_string());
+
+        let runner = MockCommandRunner::default().with_vec(
+            args,
+            CommandOutput {
+                stdout: "clean".to_string(),
+                stderr: String::new(),
+            },
+        );
+        let context = Context::test_with_command_runner(tmp.clone(), runner);
+
+        let output = repo.exec_git(&context, ["status"]);
+
+        assert_eq!(output.map(|o| o.stdout), Some("clean".to_string()));
+
+        std::fs::remove_dir_all(&tmp).ok();
     }
 }
\ No newline at end of file
@@ -3,18 +3,55 @@ use std::time::Duration;
 use std::path::{Path, PathBuf};
 use std::ffi::OsStr;
 use std::fmt::Debug;
-use crate::utils::{CommandOutput, create_command, exec_timeout};
+use crate::utils::{CommandOutput, create_command};
 use std::collections::HashMap;
 use serde::Deserialize;
 
 pub struct Repo {
     pub workdir: PathBuf,
     pub jj_closest_bookmarks: Option<JjClosestBookmarksInfo>,
+
+    /// `true` only if a `.git` directory/file sits beside `.jj` at the
+    /// workspace root. jj's internal backing store at
+    /// `.jj/repo/store/git` exists for every git-backed jj repo, colocated
+    /// or not, so its presence alone must not set this flag. This is data
+    /// only: it doesn't by itself change what the git module renders. A
+    /// coordination point that checks this flag before rendering git branch
+    /// state still needs to be added where the git and jj modules are wired
+    /// together, to avoid double-rendering VCS state in a colocated repo.
+    pub is_colocated: bool,
+
+    /// The git directory backing this jj repo: the sibling `.git` when
+    /// colocated, otherwise jj's internal `.jj/repo/store/git`. Its presence
+    /// does not imply `is_colocated`.
+    pub backing_git_dir: Option<PathBuf>,
+
+    /// Working-copy (`@`) facts that have no git equivalent, such as whether
+    /// it's empty, divergent, or still missing a description.
+    pub jj_working_copy: Option<JjWorkingCopyInfo>,
+}
+
+/// Working-copy facts jj uniquely exposes, parsed from a templated `jj log -r @`.
+#[derive(Debug, Clone)]
+pub struct JjWorkingCopyInfo {
+    /// `true` if the working-copy commit has no changes.
+    pub is_empty: bool,
+    /// `true` if multiple visible commits share the working-copy's change id.
+    pub is_divergent: bool,
+    /// `true` if the working-copy commit is immutable.
+    pub is_immutable: bool,
+    /// `true` if the working-copy commit is hidden.
+    pub is_hidden: bool,
+    /// The first line of the description, or `"(no description set)"`.
+    pub description: String,
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct JjClosestBookmarksInfo {
     pub bookmarks: Vec<BookmarkInfo>,
+    /// `true` if any local or remote bookmark on the closest commit is conflicted,
+    /// i.e. points at divergent targets and needs to be resolved before pushing.
+    pub has_conflict: bool,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -32,6 +69,52 @@ struct TrackedBookmarkOutput {
     behind: usize,
 }
 
+const WORKING_COPY_TEMPLATE: &str = r#"self.empty() ++ "\x1f"
+    ++ self.divergent() ++ "\x1f"
+    ++ self.immutable() ++ "\x1f"
+    ++ self.hidden() ++ "\x1f"
+    ++ if(description.first_line() == "", "(no description set)", description.first_line())"#;
+
+fn get_jj_working_copy_info(ctx: &Context, ignore_working_copy: &bool) -> Option<JjWorkingCopyInfo> {
+    let output = ctx
+        .exec_cmd(
+            "jj",
+            &[
+                "log",
+                "--no-graph",
+                "-r",
+                "@",
+                if *ignore_working_copy {
+                    "--ignore-working-copy"
+                } else {
+                    ""
+                },
+                "-T",
+                WORKING_COPY_TEMPLATE,
+            ],
+        )?
+        .stdout;
+
+    let mut fields = output.split('\x1f');
+    let is_empty = fields.next()?.trim() == "true";
+    let is_divergent = fields.next()?.trim() == "true";
+    let is_immutable = fields.next()?.trim() == "true";
+    let is_hidden = fields.next()?.trim() == "true";
+    let description = fields
+        .next()
+        .unwrap_or("(no description set)")
+        .trim()
+        .to_string();
+
+    Some(JjWorkingCopyInfo {
+        is_empty,
+        is_divergent,
+        is_immutable,
+        is_hidden,
+        description,
+    })
+}
+
 const CLOSEST_BOOKMARKS_TEMPLATE: &str =  r#"bookmarks.map(|b| b.normal_target().change_id() ++ "\x1f")"#;
 
 fn jujutsu_closest_template() -> String {
@@ -81,6 +164,7 @@ pub(crate) fn get_closest_jujutsu_bookmarks_info(ctx: &Context, ignore_working_c
 
     let change_id_closest = closest_bookmarks_output.split("\x1f").next().unwrap_or("");
     let mut closest_bookmarks = Vec::new();
+    let mut has_conflict = false;
 
     if !change_id_closest.is_empty() {
         let output = ctx
@@ -106,6 +190,7 @@ pub(crate) fn get_closest_jujutsu_bookmarks_info(ctx: &Context, ignore_working_c
 
         let bookmarks_str = lines.next().unwrap_or("");
         let tracked_bookmarks_str = lines.next().unwrap_or("");
+        has_conflict = lines.next().unwrap_or("").trim() == "true";
 
         let tracked_bookmarks = parse_tracked_bookmarks(tracked_bookmarks_str);
 
@@ -133,6 +218,7 @@ pub(crate) fn get_closest_jujutsu_bookmarks_info(ctx: &Context, ignore_working_c
 
     Some(JjClosestBookmarksInfo {
         bookmarks: closest_bookmarks,
+        has_conflict,
     })
 }
 
@@ -145,13 +231,38 @@ pub fn init_repo(context: &Context, cwd: &Path) -> Option<Repo> {
     let workspace_dir = cwd.ancestors().find(|path| path.join(".jj").is_dir())?;
 
     let jjbmk = get_closest_jujutsu_bookmarks_info(context, &true);
+    let jj_working_copy = get_jj_working_copy_info(context, &true);
+    let colocated_git_dir = detect_colocated_git_dir(workspace_dir);
+    let is_colocated = colocated_git_dir.is_some();
+    let backing_git_dir = colocated_git_dir.or_else(|| backing_store_git_dir(workspace_dir));
 
     Some(Repo {
         workdir: workspace_dir.into(),
         jj_closest_bookmarks: jjbmk,
+        is_colocated,
+        backing_git_dir,
+        jj_working_copy,
     })
 }
 
+/// Detects whether a jj workspace is colocated with a git repository, i.e. a
+/// `.git` directory (or worktree file) sits beside the `.jj` directory. This
+/// is the *only* signal for colocation: jj's internal backing store exists
+/// for non-colocated repos too, so checking inside `.jj` would misclassify
+/// them as colocated.
+fn detect_colocated_git_dir(workspace_dir: &Path) -> Option<PathBuf> {
+    let sibling_git_dir = workspace_dir.join(".git");
+    sibling_git_dir.exists().then_some(sibling_git_dir)
+}
+
+/// The git directory jj uses as its backing store when there's no sibling
+/// `.git`, i.e. a repo created with `jj git init`/`jj git clone` without
+/// `--colocate`. Presence here does not imply colocation.
+fn backing_store_git_dir(workspace_dir: &Path) -> Option<PathBuf> {
+    let path = workspace_dir.join(".jj/repo/store/git");
+    path.exists().then_some(path)
+}
+
 pub trait OrLog {
     type Output;
     fn or_log(self, module: &str) -> Self::Output;
@@ -168,8 +279,9 @@ impl<T, E: std::fmt::Display> OrLog for Result<T, E> {
 impl Repo {
 
 
-    /// Wrapper to execute external jj commands
-    /// At this time, mocking is not supported.
+    /// Wrapper to execute external jj commands.
+    /// Runs the command through `context.command_runner`, so tests can swap in a
+    /// `MockCommandRunner` instead of spawning a real `jj` process.
     pub fn exec_jj<T: AsRef<OsStr> + Debug>(
         &self,
         context: &Context,
@@ -177,22 +289,156 @@ impl Repo {
     ) -> Option<CommandOutput> {
         let mut command = create_command("jj").ok()?;
 
+        command.args(jj_args);
+        log::trace!("Executing jj command: {command:?}");
 
-        //command.env("GIT_OPTIONAL_LOCKS", "0").args([
-        //    OsStr::new("-C"),
-        //    context.current_dir.as_os_str(),
-        //    OsStr::new("--git-dir"),
-        //    self.path.as_os_str(),
-        //    OsStr::new("-c"),
-        //    OsStr::new(fsm_config_value),
-        //]);
+        context
+            .command_runner
+            .run(command, Duration::from_millis(context.root_config.command_timeout))
+    }
+}
 
-        command.args(jj_args);
-        log::trace!("Executing git command: {command:?}");
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::command::MockCommandRunner;
+    use std::path::PathBuf;
+
+    fn output(stdout: &str) -> CommandOutput {
+        CommandOutput {
+            stdout: stdout.to_string(),
+            stderr: String::new(),
+        }
+    }
+
+    #[test]
+    fn parses_closest_bookmarks_and_conflict_state() {
+        let runner = MockCommandRunner::default()
+            .with(
+                &[
+                    "jj",
+                    "log",
+                    "--no-graph",
+                    "-r",
+                    "heads(::@ & bookmarks())",
+                    "--ignore-working-copy",
+                    "-T",
+                    CLOSEST_BOOKMARKS_TEMPLATE,
+                ],
+                output("abc123\x1f"),
+            )
+            .with(
+                &[
+                    "jj",
+                    "log",
+                    "--no-graph",
+                    "-r",
+                    "abc123",
+                    "--ignore-working-copy",
+                    "-T",
+                    &jujutsu_closest_template(),
+                ],
+                output("main\x1e\n{\"name\":\"main\",\"ahead\":2,\"behind\":1}\ntrue"),
+            );
+        let context = Context::test_with_command_runner(PathBuf::from("."), runner);
+
+        let info = get_closest_jujutsu_bookmarks_info(&context, &true).unwrap();
+
+        assert!(info.has_conflict);
+        assert_eq!(info.bookmarks.len(), 1);
+        assert_eq!(info.bookmarks[0].name, "main");
+        assert_eq!(info.bookmarks[0].remote_ahead, 2);
+        assert_eq!(info.bookmarks[0].remote_behind, 1);
+        assert!(info.bookmarks[0].is_tracked);
+    }
+
+    #[test]
+    fn reports_no_conflict_when_no_bookmarks_are_close() {
+        let runner = MockCommandRunner::default().with(
+            &[
+                "jj",
+                "log",
+                "--no-graph",
+                "-r",
+                "heads(::@ & bookmarks())",
+                "--ignore-working-copy",
+                "-T",
+                CLOSEST_BOOKMARKS_TEMPLATE,
+            ],
+            output(""),
+        );
+        let context = Context::test_with_command_runner(PathBuf::from("."), runner);
+
+        let info = get_closest_jujutsu_bookmarks_info(&context, &true).unwrap();
+
+        assert!(!info.has_conflict);
+        assert!(info.bookmarks.is_empty());
+    }
+
+    #[test]
+    fn parses_working_copy_info() {
+        let runner = MockCommandRunner::default().with(
+            &[
+                "jj",
+                "log",
+                "--no-graph",
+                "-r",
+                "@",
+                "--ignore-working-copy",
+                "-T",
+                WORKING_COPY_TEMPLATE,
+            ],
+            output("false\x1ftrue\x1ffalse\x1ffalse\x1fwip: add tests"),
+        );
+        let context = Context::test_with_command_runner(PathBuf::from("."), runner);
+
+        let info = get_jj_working_copy_info(&context, &true).unwrap();
+
+        assert!(!info.is_empty);
+        assert!(info.is_divergent);
+        assert!(!info.is_immutable);
+        assert!(!info.is_hidden);
+        assert_eq!(info.description, "wip: add tests");
+    }
+
+    #[test]
+    fn detects_colocation_from_sibling_git_dir() {
+        let tmp = std::env::temp_dir().join(format!(
+            "starship-jj-colocate-test-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(tmp.join(".jj")).unwrap();
+        std::fs::create_dir_all(tmp.join(".git")).unwrap();
+
+        let backing_git_dir = detect_colocated_git_dir(&tmp);
+
+        assert_eq!(backing_git_dir, Some(tmp.join(".git")));
+
+        std::fs::remove_dir_all(&tmp).ok();
+    }
+
+    #[test]
+    fn does_not_report_colocation_for_a_backing_store_only_git_dir() {
+        let tmp = std::env::temp_dir().join(format!(
+            "starship-jj-no-colocate-test-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(tmp.join(".jj/repo/store/git")).unwrap();
+
+        assert_eq!(detect_colocated_git_dir(&tmp), None);
+        assert_eq!(
+            backing_store_git_dir(&tmp),
+            Some(tmp.join(".jj/repo/store/git"))
+        );
 
-        exec_timeout(
-            &mut command,
-            Duration::from_millis(context.root_config.command_timeout),
-        )
+        std::fs::remove_dir_all(&tmp).ok();
     }
 }
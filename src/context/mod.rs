@@ -0,0 +1,72 @@
+pub mod command;
+pub mod git;
+pub mod jj;
+
+use command::{CommandRunner, SystemCommandRunner};
+use crate::utils::{CommandOutput, create_command};
+use std::ffi::OsStr;
+use std::fmt::Debug;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The subset of starship's resolved configuration that the git/jj context
+/// modules care about.
+pub struct RootConfig {
+    /// Timeout, in milliseconds, for any external command starship runs.
+    pub command_timeout: u64,
+}
+
+/// Shared state threaded through module rendering: the directory being
+/// rendered for, the resolved configuration, and the command layer used to
+/// shell out to `git`/`jj`.
+pub struct Context {
+    pub current_dir: PathBuf,
+    pub root_config: RootConfig,
+
+    /// Executes the commands run by `exec_cmd` and by `git::Repo::exec_git`/
+    /// `jj::Repo::exec_jj`. Defaults to `SystemCommandRunner`; tests swap in a
+    /// `command::MockCommandRunner` to replay canned output instead of
+    /// spawning a real process.
+    pub command_runner: Arc<dyn CommandRunner>,
+}
+
+impl Context {
+    /// Runs `program` with `args` through `command_runner`.
+    pub fn exec_cmd<T: AsRef<OsStr> + Debug>(
+        &self,
+        program: &str,
+        args: impl IntoIterator<Item = T>,
+    ) -> Option<CommandOutput> {
+        let mut command = create_command(program).ok()?;
+        command.args(args);
+        self.command_runner
+            .run(command, Duration::from_millis(self.root_config.command_timeout))
+    }
+
+    #[cfg(test)]
+    pub(crate) fn test_with_command_runner(
+        current_dir: PathBuf,
+        command_runner: command::MockCommandRunner,
+    ) -> Self {
+        Self {
+            current_dir,
+            root_config: RootConfig {
+                command_timeout: 500,
+            },
+            command_runner: Arc::new(command_runner),
+        }
+    }
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Self {
+            current_dir: PathBuf::new(),
+            root_config: RootConfig {
+                command_timeout: 500,
+            },
+            command_runner: Arc::new(SystemCommandRunner),
+        }
+    }
+}